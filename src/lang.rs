@@ -0,0 +1,168 @@
+use tree_sitter::Language;
+
+/// A pluggable grammar: how to recognize, parse, and measure functions in
+/// one source language. The directory scan picks the spec matching a file's
+/// extension, and `process_file` uses its query to find functions and its
+/// parameter node kinds to compute arity, since those are grammar-specific.
+pub struct LanguageSpec {
+    pub name: &'static str,
+    pub extensions: &'static [&'static str],
+    pub grammar: fn() -> Language,
+    pub query: &'static str,
+    /// Node kinds under a function's parameter list that count as a
+    /// positional argument.
+    pub parameter_kinds: &'static [&'static str],
+    /// Node kinds that represent an implicit receiver (e.g. Rust's
+    /// `self_parameter`) and are never counted.
+    pub self_kinds: &'static [&'static str],
+    /// Identifier names that mark the leading parameter as an implicit
+    /// receiver in languages where, unlike Rust, it's just an ordinary
+    /// parameter by convention rather than its own grammar node (Python's
+    /// `self`/`cls`). Checked only against the first parameter.
+    pub self_param_names: &'static [&'static str],
+}
+
+const RUST_QUERY: &str = r#"
+    (function_item
+      name: (identifier) @function_name
+      type_parameters: (type_parameters)? @generics
+      parameters: (parameters) @params
+      return_type: (_)? @return_type)
+
+    (function_signature_item
+      name: (identifier) @function_name
+      type_parameters: (type_parameters)? @generics
+      parameters: (parameters) @params
+      return_type: (_)? @return_type)
+    "#;
+
+// A pointer-returning function's declarator is wrapped in one
+// `pointer_declarator` per `*` (`int *f(...)`, `int **f(...)`, ...); we
+// unwrap a handful of levels explicitly since tree-sitter queries can't
+// express arbitrary-depth nesting. Beyond that depth the function is
+// silently skipped.
+const C_QUERY: &str = r#"
+    (function_definition
+      declarator: (function_declarator
+        declarator: (identifier) @function_name
+        parameters: (parameter_list) @params))
+
+    (function_definition
+      declarator: (pointer_declarator
+        declarator: (function_declarator
+          declarator: (identifier) @function_name
+          parameters: (parameter_list) @params)))
+
+    (function_definition
+      declarator: (pointer_declarator
+        declarator: (pointer_declarator
+          declarator: (function_declarator
+            declarator: (identifier) @function_name
+            parameters: (parameter_list) @params))))
+    "#;
+
+const PYTHON_QUERY: &str = r#"
+    (function_definition
+      name: (identifier) @function_name
+      parameters: (parameters) @params
+      return_type: (_)? @return_type)
+    "#;
+
+// Intentionally not captured: anonymous arrow/function expressions with no
+// enclosing `variable_declarator` (e.g. inline callbacks like
+// `arr.map((a, b) => ...)`), and single-parameter arrow functions written
+// without parens (`x => x`), whose parameter is a bare `identifier` rather
+// than a `formal_parameters` node. Neither has both a name and a uniform
+// params node to report.
+const JAVASCRIPT_QUERY: &str = r#"
+    (function_declaration
+      name: (identifier) @function_name
+      parameters: (formal_parameters) @params)
+
+    (method_definition
+      name: (property_identifier) @function_name
+      parameters: (formal_parameters) @params)
+
+    (variable_declarator
+      name: (identifier) @function_name
+      value: (arrow_function
+        parameters: (formal_parameters) @params))
+
+    (variable_declarator
+      name: (identifier) @function_name
+      value: (function_expression
+        parameters: (formal_parameters) @params))
+    "#;
+
+pub const LANGUAGES: &[LanguageSpec] = &[
+    LanguageSpec {
+        name: "rust",
+        extensions: &["rs"],
+        grammar: || tree_sitter_rust::LANGUAGE.into(),
+        query: RUST_QUERY,
+        parameter_kinds: &["parameter"],
+        self_kinds: &["self_parameter"],
+        self_param_names: &[],
+    },
+    LanguageSpec {
+        name: "c",
+        extensions: &["c", "h"],
+        grammar: || tree_sitter_c::LANGUAGE.into(),
+        query: C_QUERY,
+        parameter_kinds: &["parameter_declaration"],
+        self_kinds: &[],
+        self_param_names: &[],
+    },
+    LanguageSpec {
+        name: "cpp",
+        extensions: &["cc", "cpp", "cxx", "hpp", "hh"],
+        grammar: || tree_sitter_cpp::LANGUAGE.into(),
+        query: C_QUERY,
+        parameter_kinds: &["parameter_declaration"],
+        self_kinds: &[],
+        self_param_names: &[],
+    },
+    LanguageSpec {
+        name: "python",
+        extensions: &["py"],
+        grammar: || tree_sitter_python::LANGUAGE.into(),
+        query: PYTHON_QUERY,
+        parameter_kinds: &[
+            "identifier",
+            "typed_parameter",
+            "default_parameter",
+            "typed_default_parameter",
+            "list_splat_pattern",
+            "dictionary_splat_pattern",
+        ],
+        self_kinds: &[],
+        // Python has no receiver grammar node: `self`/`cls` is just the
+        // leading parameter by convention (absent entirely for
+        // `@staticmethod`s, which this tool can't distinguish from free
+        // functions, so there's nothing to skip there).
+        self_param_names: &["self", "cls"],
+    },
+    LanguageSpec {
+        name: "javascript",
+        extensions: &["js", "jsx", "mjs"],
+        grammar: || tree_sitter_javascript::LANGUAGE.into(),
+        query: JAVASCRIPT_QUERY,
+        parameter_kinds: &[
+            "identifier",
+            "assignment_pattern",
+            "rest_pattern",
+            "object_pattern",
+            "array_pattern",
+        ],
+        self_kinds: &[],
+        // Unlike Python, JS methods have no implicit receiver parameter at
+        // all (`this` is not part of `formal_parameters`), so there's
+        // nothing here to special-case.
+        self_param_names: &[],
+    },
+];
+
+/// Looks up a grammar by its `--lang` name (e.g. "rust", "python").
+pub fn by_name(name: &str) -> Option<&'static LanguageSpec> {
+    LANGUAGES.iter().find(|lang| lang.name == name)
+}
@@ -1,89 +1,196 @@
-use std::collections::BinaryHeap;
+use serde::Serialize;
 use std::env;
 use std::fmt::Display;
 use std::fs;
+use std::io;
 use std::path::Path;
 use std::path::PathBuf;
 use tree_sitter::StreamingIterator;
 use tree_sitter::{Parser, Query, QueryCursor};
-use walkdir::WalkDir;
-
-const QUERY_SOURCE: &str = r#"
-    (function_item
-      name: (identifier) @function_name
-      parameters: (parameters) @params)
-    
-    (function_signature_item
-      name: (identifier) @function_name
-      parameters: (parameters) @params)
-    "#;
-
-fn parse_args() -> (PathBuf, usize) {
-    // Parse command line arguments
+
+mod lang;
+mod output;
+mod rank;
+mod repl;
+mod scan;
+mod topk;
+
+use lang::LanguageSpec;
+use output::OutputFormat;
+use rank::{RankConfig, SortKey};
+use topk::TopK;
+
+#[derive(Debug)]
+struct Args {
+    directory: PathBuf,
+    min_args: usize,
+    format: OutputFormat,
+    output: Option<PathBuf>,
+    threads: usize,
+    top: Option<usize>,
+    no_ignore: bool,
+    lang: Option<String>,
+    interactive: bool,
+    sort_by: SortKey,
+    weighted: bool,
+}
+
+fn default_threads() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+fn parse_args() -> Args {
     let args: Vec<String> = env::args().collect();
-    if args.len() < 3 {
-        eprintln!("Usage: {} <directory> <min_args>", args[0]);
+    parse_args_from(&args).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        eprintln!(
+            "Usage: {} <directory> <min_args> [--format json|text|csv] [--output <PATH>] [--threads <N>] [--top <K>] [--no-ignore] [--lang <NAME>] [--interactive] [--sort-by arity|generics|lifetimes] [--weighted]",
+            args.first().map(String::as_str).unwrap_or("rust-arity-scan")
+        );
         std::process::exit(1);
+    })
+}
+
+/// Parses `args` (as if from `env::args()`, i.e. `args[0]` is the program
+/// name) into `Args`, or an error message describing what was wrong.
+/// Pulled out of `parse_args` so argument parsing can be tested without
+/// going through `env::args()` or `process::exit`.
+fn parse_args_from(args: &[String]) -> Result<Args, String> {
+    if args.len() < 3 {
+        return Err("expected at least <directory> and <min_args>".to_string());
     }
 
     let directory = PathBuf::from(&args[1]);
     let min_args = args[2]
         .parse::<usize>()
-        .expect("Minimum arguments must be a number");
+        .map_err(|_| "min_args must be a number".to_string())?;
+
+    let mut format = OutputFormat::Text;
+    let mut output = None;
+    let mut threads = default_threads();
+    let mut top = None;
+    let mut no_ignore = false;
+    let mut lang = None;
+    let mut interactive = false;
+    let mut sort_by = SortKey::Arity;
+    let mut weighted = false;
+
+    let mut rest = args[3..].iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--format" => {
+                let value = rest.next().ok_or("--format requires a value")?;
+                format = value.parse()?;
+            }
+            "--output" => {
+                let value = rest.next().ok_or("--output requires a value")?;
+                output = Some(PathBuf::from(value));
+            }
+            "--threads" => {
+                let value = rest.next().ok_or("--threads requires a value")?;
+                threads = value
+                    .parse()
+                    .map_err(|_| "--threads must be a number".to_string())?;
+            }
+            "--top" => {
+                let value = rest.next().ok_or("--top requires a value")?;
+                top = Some(
+                    value
+                        .parse()
+                        .map_err(|_| "--top must be a number".to_string())?,
+                );
+            }
+            "--no-ignore" => {
+                no_ignore = true;
+            }
+            "--lang" => {
+                let value = rest.next().ok_or("--lang requires a value")?;
+                lang = Some(value.clone());
+            }
+            "--interactive" => {
+                interactive = true;
+            }
+            "--sort-by" => {
+                let value = rest.next().ok_or("--sort-by requires a value")?;
+                sort_by = value.parse()?;
+            }
+            "--weighted" => {
+                weighted = true;
+            }
+            other => return Err(format!("Unrecognized argument: {other}")),
+        }
+    }
 
-    (directory, min_args)
+    Ok(Args {
+        directory,
+        min_args,
+        format,
+        output,
+        threads,
+        top,
+        no_ignore,
+        lang,
+        interactive,
+        sort_by,
+        weighted,
+    })
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let (directory, min_args) = parse_args();
+    let args = parse_args();
 
-    let mut parser = Parser::new();
-    parser.set_language(&tree_sitter_rust::LANGUAGE.into())?;
+    let rank = RankConfig {
+        key: args.sort_by,
+        weighted: args.weighted,
+    };
 
-    let query = Query::new(&tree_sitter_rust::LANGUAGE.into(), QUERY_SOURCE)?;
+    let (bucket, total_files) = scan::scan_directory(
+        &args.directory,
+        args.min_args,
+        args.threads,
+        args.top,
+        args.no_ignore,
+        args.lang.as_deref(),
+        rank,
+    )?;
 
-    let mut total_files = 0;
+    let results: Vec<FnInfo> = bucket.into_sorted_vec();
 
-    let mut bucket = BinaryHeap::new();
-
-    for entry in WalkDir::new(&directory)
-        .follow_links(true)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().extension().map_or(false, |ext| ext == "rs"))
-    {
-        let path = entry.path();
-        let file_count = process_file(
-            &directory,
-            &path,
-            &mut parser,
-            &query,
-            min_args,
-            &mut bucket,
-        )?;
-        total_files += file_count;
+    if args.interactive {
+        return repl::run(results);
     }
 
-    for el in bucket {
-        println!("{el}");
-    }
+    let mut writer: Box<dyn io::Write> = match &args.output {
+        Some(path) => Box::new(fs::File::create(path)?),
+        None => Box::new(io::stdout()),
+    };
 
-    println!("\nFound {total_files} functions with more than {min_args} arguments");
+    output::write_report(&results, args.format, total_files, args.min_args, &mut writer)?;
 
     Ok(())
 }
 
-#[derive(Debug, Hash, PartialEq, Eq)]
+#[derive(Debug, Hash, PartialEq, Eq, Serialize)]
 struct FnInfo {
-    path: PathBuf,
-    name: String,
-    arity: usize,
-    line: usize,
+    pub(crate) path: PathBuf,
+    pub(crate) name: String,
+    pub(crate) arity: usize,
+    pub(crate) line: usize,
+    pub(crate) generics: usize,
+    pub(crate) lifetimes: usize,
+    pub(crate) has_return_type: bool,
+    pub(crate) self_kind: SelfKind,
 }
 
+/// Arity-only ordering. This is *not* how results are ranked — `RankConfig`
+/// (`--sort-by`/`--weighted`) does that — it only breaks ties between
+/// equally-scored entries inside `TopK`, so it stays fixed on arity
+/// regardless of the selected rank key.
 impl PartialOrd for FnInfo {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.arity.partial_cmp(&other.arity)
+        Some(self.cmp(other))
     }
 }
 impl Ord for FnInfo {
@@ -96,46 +203,83 @@ impl Display for FnInfo {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{}:{}: fn {}/{}",
+            "{}:{}: fn {}/{} (generics={}, lifetimes={}, returns={}, self={})",
             self.path.display(),
             self.line,
             self.name,
-            self.arity
+            self.arity,
+            self.generics,
+            self.lifetimes,
+            self.has_return_type,
+            self.self_kind,
         )
     }
 }
 
-fn process_file(
+/// Whether, and how, a function receives an implicit instance receiver
+/// (Rust's `self`, `&self`, `&mut self`).
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum SelfKind {
+    None,
+    ByValue,
+    ByRef,
+    ByMutRef,
+}
+
+impl Display for SelfKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            SelfKind::None => "none",
+            SelfKind::ByValue => "self",
+            SelfKind::ByRef => "&self",
+            SelfKind::ByMutRef => "&mut self",
+        };
+        write!(f, "{s}")
+    }
+}
+
+pub(crate) fn process_file(
     base: &Path,
     path: &Path,
     parser: &mut Parser,
     query: &Query,
+    lang: &LanguageSpec,
     min_args: usize,
-    bucket: &mut BinaryHeap<FnInfo>,
+    bucket: &mut TopK,
 ) -> Result<usize, Box<dyn std::error::Error>> {
     let source_code = fs::read_to_string(path)?;
     let tree = parser
         .parse(&source_code, None)
-        .expect(&format!("FAILED TO PARSE file {}", &source_code));
+        .unwrap_or_else(|| panic!("failed to parse {}", path.display()));
 
     let mut cursor = QueryCursor::new();
     let mut matches = cursor.matches(query, tree.root_node(), source_code.as_bytes());
 
     let function_name_idx = query.capture_index_for_name("function_name").unwrap_or(0);
     let params_idx = query.capture_index_for_name("params").unwrap_or(0);
+    let generics_idx = query.capture_index_for_name("generics");
+    let return_type_idx = query.capture_index_for_name("return_type");
 
     let mut count = 0;
 
     while let Some(m) = matches.next() {
         let name_capture = m.captures.iter().find(|c| c.index == function_name_idx);
-
         let params_capture = m.captures.iter().find(|c| c.index == params_idx);
+        let generics_capture =
+            generics_idx.and_then(|idx| m.captures.iter().find(|c| c.index == idx));
+        let return_type_capture =
+            return_type_idx.and_then(|idx| m.captures.iter().find(|c| c.index == idx));
 
         if let (Some(name), Some(params)) = (name_capture, params_capture) {
             let name = source_code[name.node.byte_range()].to_owned();
             let params_node = params.node;
 
-            let arity = count_parameters(&params_node);
+            let (arity, self_kind) =
+                analyze_parameters(&params_node, source_code.as_bytes(), lang);
+            let (generics, lifetimes) =
+                count_generics_and_lifetimes(generics_capture.map(|c| c.node));
+            let has_return_type = return_type_capture.is_some();
 
             let path = path.strip_prefix(base).unwrap().to_path_buf();
             if arity > min_args {
@@ -145,6 +289,10 @@ fn process_file(
                     name,
                     arity,
                     line,
+                    generics,
+                    lifetimes,
+                    has_return_type,
+                    self_kind,
                 });
                 count += 1;
             }
@@ -154,19 +302,181 @@ fn process_file(
     Ok(count)
 }
 
-fn count_parameters(params_node: &tree_sitter::Node) -> usize {
+/// Counts positional parameters and classifies the implicit receiver (if
+/// any), using `lang`'s grammar-specific node kinds.
+fn analyze_parameters(
+    params_node: &tree_sitter::Node,
+    source: &[u8],
+    lang: &LanguageSpec,
+) -> (usize, SelfKind) {
     let mut count = 0;
+    let mut self_kind = SelfKind::None;
+    let mut seen_param = false;
     let mut cursor = params_node.walk();
 
-    // Count each parameter (skipping self if it's a method)
     for child in params_node.children(&mut cursor) {
-        if child.kind() == "self_parameter" {
-            continue; // Skip self parameter
+        let kind = child.kind();
+        if lang.self_kinds.contains(&kind) {
+            // Don't key off a raw `&mut` text prefix: a lifetime between the
+            // `&` and `mut` (`&'a mut self`) pushes `mut` further into the
+            // text, so check for the grammar's own `mutable_specifier`/`&`
+            // child nodes instead.
+            let mut by_ref = false;
+            let mut mutable = false;
+            let mut self_cursor = child.walk();
+            for self_child in child.children(&mut self_cursor) {
+                match self_child.kind() {
+                    "&" => by_ref = true,
+                    "mutable_specifier" => mutable = true,
+                    _ => {}
+                }
+            }
+            // A `mutable_specifier` without `&` is a by-value `mut self`.
+            self_kind = if by_ref && mutable {
+                SelfKind::ByMutRef
+            } else if by_ref {
+                SelfKind::ByRef
+            } else {
+                SelfKind::ByValue
+            };
+            continue;
         }
-        if child.kind() == "parameter" {
+        if lang.parameter_kinds.contains(&kind) {
+            let text = std::str::from_utf8(&source[child.byte_range()])
+                .unwrap_or("")
+                .trim();
+
+            // C/C++ parse a lone `(void)` parameter list as a single
+            // `parameter_declaration` with a `void` type and no declarator,
+            // meaning "takes no arguments" rather than one argument.
+            if kind == "parameter_declaration" && text == "void" {
+                continue;
+            }
+            // Languages like Python have no receiver grammar node: `self`/
+            // `cls` is just the leading parameter by convention.
+            if !seen_param && lang.self_param_names.contains(&text) {
+                self_kind = SelfKind::ByValue;
+                seen_param = true;
+                continue;
+            }
+
+            seen_param = true;
             count += 1;
         }
     }
 
-    count
+    (count, self_kind)
+}
+
+/// Counts generic type/const parameters and lifetime parameters inside a
+/// captured `type_parameters` node (Rust-specific; other grammars simply
+/// don't capture `generics`, so this always returns `(0, 0)` for them).
+fn count_generics_and_lifetimes(generics_node: Option<tree_sitter::Node>) -> (usize, usize) {
+    let Some(node) = generics_node else {
+        return (0, 0);
+    };
+
+    let mut generics = 0;
+    let mut lifetimes = 0;
+    let mut cursor = node.walk();
+
+    for child in node.children(&mut cursor) {
+        match child.kind() {
+            "lifetime_parameter" => lifetimes += 1,
+            "type_parameter" | "constrained_type_parameter" | "optional_type_parameter"
+            | "const_parameter" => generics += 1,
+            _ => {}
+        }
+    }
+
+    (generics, lifetimes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(extra: &[&str]) -> Vec<String> {
+        let mut v = vec!["rust-arity-scan".to_string(), "src".to_string(), "2".to_string()];
+        v.extend(extra.iter().map(|s| s.to_string()));
+        v
+    }
+
+    #[test]
+    fn parses_required_positional_args() {
+        let parsed = parse_args_from(&args(&[])).unwrap();
+        assert_eq!(parsed.directory, PathBuf::from("src"));
+        assert_eq!(parsed.min_args, 2);
+        assert_eq!(parsed.format, OutputFormat::Text);
+    }
+
+    #[test]
+    fn parses_flags_with_values() {
+        let parsed =
+            parse_args_from(&args(&["--format", "json", "--top", "5", "--sort-by", "generics"]))
+                .unwrap();
+        assert_eq!(parsed.format, OutputFormat::Json);
+        assert_eq!(parsed.top, Some(5));
+        assert_eq!(parsed.sort_by, SortKey::Generics);
+    }
+
+    #[test]
+    fn parses_boolean_flags() {
+        let parsed = parse_args_from(&args(&["--no-ignore", "--interactive", "--weighted"])).unwrap();
+        assert!(parsed.no_ignore);
+        assert!(parsed.interactive);
+        assert!(parsed.weighted);
+    }
+
+    #[test]
+    fn rejects_too_few_positional_args() {
+        let err = parse_args_from(&["rust-arity-scan".to_string()]).unwrap_err();
+        assert!(err.contains("directory"));
+    }
+
+    #[test]
+    fn rejects_unknown_flag_value() {
+        let err = parse_args_from(&args(&["--format", "yaml"])).unwrap_err();
+        assert!(err.contains("yaml"));
+    }
+
+    #[test]
+    fn rejects_unrecognized_argument() {
+        let err = parse_args_from(&args(&["--bogus"])).unwrap_err();
+        assert!(err.contains("--bogus"));
+    }
+
+    #[test]
+    fn process_file_extracts_generics_lifetimes_and_self_kind_from_real_source() {
+        let dir = std::env::temp_dir().join(format!("rust_arity_scan_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("sample.rs");
+        fs::write(
+            &path,
+            "impl S {\n    fn f<'a, 'b, T>(&'a mut self, a: i32, b: i32) -> T { todo!() }\n}\n",
+        )
+        .unwrap();
+
+        let spec = crate::lang::by_name("rust").unwrap();
+        let mut parser = Parser::new();
+        parser.set_language(&(spec.grammar)()).unwrap();
+        let query = Query::new(&(spec.grammar)(), spec.query).unwrap();
+
+        let mut bucket = TopK::new(
+            None,
+            RankConfig {
+                key: SortKey::Arity,
+                weighted: false,
+            },
+        );
+        process_file(&dir, &path, &mut parser, &query, spec, 0, &mut bucket).unwrap();
+
+        let results = bucket.into_sorted_vec();
+        let info = results.iter().find(|f| f.name == "f").unwrap();
+        assert_eq!(info.generics, 1);
+        assert_eq!(info.lifetimes, 2);
+        assert_eq!(info.self_kind, SelfKind::ByMutRef);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
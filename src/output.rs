@@ -0,0 +1,87 @@
+use std::error::Error;
+use std::io::Write;
+use std::str::FromStr;
+
+use crate::FnInfo;
+
+/// Output formats supported by `--format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            other => Err(format!(
+                "unknown format '{other}' (expected text, json, or csv)"
+            )),
+        }
+    }
+}
+
+/// Renders the collected `FnInfo` records in the requested format, writing
+/// to `out` (either stdout or a `--output` file).
+pub fn write_report(
+    results: &[FnInfo],
+    format: OutputFormat,
+    total_files: usize,
+    min_args: usize,
+    out: &mut dyn Write,
+) -> Result<(), Box<dyn Error>> {
+    match format {
+        OutputFormat::Text => {
+            for info in results {
+                writeln!(out, "{info}")?;
+            }
+            writeln!(
+                out,
+                "\nFound {total_files} functions with more than {min_args} arguments"
+            )?;
+        }
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut *out, results)?;
+            writeln!(out)?;
+        }
+        OutputFormat::Csv => {
+            writeln!(
+                out,
+                "path,name,arity,line,generics,lifetimes,has_return_type,self_kind"
+            )?;
+            for info in results {
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{},{}",
+                    csv_field(&info.path.display().to_string()),
+                    csv_field(&info.name),
+                    info.arity,
+                    info.line,
+                    info.generics,
+                    info.lifetimes,
+                    info.has_return_type,
+                    info.self_kind,
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Quotes a CSV field per RFC 4180 if it contains a comma, quote, or
+/// newline, doubling any embedded quotes. Paths and function names are the
+/// only fields that can contain such characters.
+fn csv_field(value: &str) -> String {
+    if value.contains(['"', ',', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
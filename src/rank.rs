@@ -0,0 +1,99 @@
+use std::str::FromStr;
+
+use crate::FnInfo;
+
+/// Which signature dimension ranks functions against each other for
+/// `--top` eviction and final ordering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Arity,
+    Generics,
+    Lifetimes,
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "arity" => Ok(SortKey::Arity),
+            "generics" => Ok(SortKey::Generics),
+            "lifetimes" => Ok(SortKey::Lifetimes),
+            other => Err(format!(
+                "unknown sort key '{other}' (expected arity, generics, or lifetimes)"
+            )),
+        }
+    }
+}
+
+/// Configures how candidates are scored: by a single signature dimension,
+/// or (with `weighted`) by the sum of positional args, generics, and
+/// lifetimes — a rougher but fuller "complexity" signal than raw arity.
+#[derive(Debug, Clone, Copy)]
+pub struct RankConfig {
+    pub key: SortKey,
+    pub weighted: bool,
+}
+
+impl RankConfig {
+    pub fn score(&self, info: &FnInfo) -> usize {
+        if self.weighted {
+            return info.arity + info.generics + info.lifetimes;
+        }
+
+        match self.key {
+            SortKey::Arity => info.arity,
+            SortKey::Generics => info.generics,
+            SortKey::Lifetimes => info.lifetimes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SelfKind;
+
+    fn fn_info(arity: usize, generics: usize, lifetimes: usize) -> FnInfo {
+        FnInfo {
+            path: "f.rs".into(),
+            name: "f".to_string(),
+            arity,
+            line: 1,
+            generics,
+            lifetimes,
+            has_return_type: false,
+            self_kind: SelfKind::None,
+        }
+    }
+
+    #[test]
+    fn score_picks_the_selected_dimension() {
+        let info = fn_info(2, 3, 4);
+        assert_eq!(
+            RankConfig { key: SortKey::Arity, weighted: false }.score(&info),
+            2
+        );
+        assert_eq!(
+            RankConfig { key: SortKey::Generics, weighted: false }.score(&info),
+            3
+        );
+        assert_eq!(
+            RankConfig { key: SortKey::Lifetimes, weighted: false }.score(&info),
+            4
+        );
+    }
+
+    #[test]
+    fn weighted_score_sums_all_dimensions_regardless_of_key() {
+        let info = fn_info(2, 3, 4);
+        let rank = RankConfig { key: SortKey::Lifetimes, weighted: true };
+        assert_eq!(rank.score(&info), 9);
+    }
+
+    #[test]
+    fn sort_key_from_str_rejects_unknown_values() {
+        assert!("arity".parse::<SortKey>().is_ok());
+        assert!("bogus".parse::<SortKey>().is_err());
+    }
+}
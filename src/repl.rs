@@ -0,0 +1,97 @@
+use std::error::Error;
+use std::fs;
+use std::io::{self, BufRead, Write};
+
+use crate::output::{self, OutputFormat};
+use crate::FnInfo;
+
+/// Line-based query shell over an already-scanned result set. Keeps
+/// `results` in memory and lets the user narrow, sort, and export it
+/// without rescanning the codebase. Supported commands:
+///
+///   min <N>        keep only functions with arity >= N
+///   filter <SUB>   keep only functions whose name or path contains SUB
+///   sort name|arity|path
+///   top <N>        print the first N current results (default 10)
+///   export <PATH>  write the current results as JSON to PATH
+///   help, quit
+pub fn run(mut results: Vec<FnInfo>) -> Result<(), Box<dyn Error>> {
+    println!(
+        "Entering interactive mode with {} result(s). Type 'help' for commands, 'quit' to exit.",
+        results.len()
+    );
+
+    let stdin = io::stdin();
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            break; // EOF
+        }
+
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut parts = line.split_whitespace();
+        let command = parts.next().unwrap_or("");
+        let rest: Vec<&str> = parts.collect();
+
+        match command {
+            "quit" | "exit" => break,
+            "help" => print_help(),
+            "min" => match rest.first().and_then(|s| s.parse::<usize>().ok()) {
+                Some(n) => {
+                    results.retain(|f| f.arity >= n);
+                    println!("{} result(s) remain", results.len());
+                }
+                None => eprintln!("usage: min <N>"),
+            },
+            "filter" => match rest.first() {
+                Some(substring) => {
+                    results.retain(|f| {
+                        f.name.contains(substring) || f.path.to_string_lossy().contains(substring)
+                    });
+                    println!("{} result(s) remain", results.len());
+                }
+                None => eprintln!("usage: filter <SUBSTRING>"),
+            },
+            "sort" => match rest.first().copied() {
+                Some("name") => results.sort_by(|a, b| a.name.cmp(&b.name)),
+                Some("arity") => results.sort_by_key(|f| std::cmp::Reverse(f.arity)),
+                Some("path") => results.sort_by(|a, b| a.path.cmp(&b.path)),
+                _ => eprintln!("usage: sort name|arity|path"),
+            },
+            "top" => {
+                let n = rest.first().and_then(|s| s.parse::<usize>().ok()).unwrap_or(10);
+                for info in results.iter().take(n) {
+                    println!("{info}");
+                }
+            }
+            "export" => match rest.first() {
+                Some(path) => {
+                    let mut file = fs::File::create(path)?;
+                    output::write_report(&results, OutputFormat::Json, results.len(), 0, &mut file)?;
+                    println!("wrote {} result(s) to {path}", results.len());
+                }
+                None => eprintln!("usage: export <PATH>"),
+            },
+            other => eprintln!("unknown command '{other}' (type 'help')"),
+        }
+    }
+
+    Ok(())
+}
+
+fn print_help() {
+    println!("commands:");
+    println!("  min <N>               keep only functions with arity >= N");
+    println!("  filter <SUBSTRING>    keep only functions whose name or path contains SUBSTRING");
+    println!("  sort name|arity|path  sort the current results");
+    println!("  top <N>               print the first N current results (default 10)");
+    println!("  export <PATH>         write the current results as JSON to PATH");
+    println!("  quit                  exit interactive mode");
+}
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+
+use ignore::WalkBuilder;
+use tree_sitter::{Parser, Query};
+
+use crate::lang::{self, LanguageSpec};
+use crate::process_file;
+use crate::rank::RankConfig;
+use crate::topk::TopK;
+
+/// Walks `directory` for source files in any registered language and scans
+/// them for functions whose arity exceeds `min_args`, fanning the work out
+/// across `threads` worker threads. Each worker owns its own `Parser`
+/// (neither `Parser` nor `QueryCursor` is `Sync`), switching grammars as it
+/// crosses language boundaries, and reports its matches back over a
+/// channel, which is merged into a single `TopK` here. `top` bounds how
+/// many matches (per worker and overall) are kept in memory at once, and
+/// `rank` picks which signature dimension decides which matches survive
+/// that bound. `no_ignore` disables `.gitignore`/`.ignore`/hidden-dir
+/// filtering so the walk covers everything under `directory`, not just
+/// first-party source. `lang_filter`, when set, restricts the scan to a
+/// single `--lang` name.
+pub fn scan_directory(
+    directory: &Path,
+    min_args: usize,
+    threads: usize,
+    top: Option<usize>,
+    no_ignore: bool,
+    lang_filter: Option<&str>,
+    rank: RankConfig,
+) -> Result<(TopK, usize), Box<dyn Error>> {
+    let threads = threads.max(1);
+
+    let languages: Vec<&'static LanguageSpec> = match lang_filter {
+        Some(name) => {
+            vec![lang::by_name(name).ok_or_else(|| format!("unknown language '{name}'"))?]
+        }
+        None => lang::LANGUAGES.iter().collect(),
+    };
+
+    let files = collect_source_files(directory, no_ignore, &languages);
+    let chunks = partition(files, threads);
+
+    let (tx, rx) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for chunk in chunks {
+            let tx = tx.clone();
+            scope.spawn(move || {
+                let mut parser = Parser::new();
+                let mut queries: HashMap<&str, Query> = HashMap::new();
+                let mut loaded_grammar: Option<&str> = None;
+
+                let mut local_bucket = TopK::new(top, rank);
+                let mut local_count = 0;
+                for (path, spec) in chunk {
+                    if loaded_grammar != Some(spec.name) {
+                        parser
+                            .set_language(&(spec.grammar)())
+                            .unwrap_or_else(|e| panic!("failed to load {} grammar: {e}", spec.name));
+                        loaded_grammar = Some(spec.name);
+                    }
+
+                    let query = queries.entry(spec.name).or_insert_with(|| {
+                        Query::new(&(spec.grammar)(), spec.query)
+                            .unwrap_or_else(|e| panic!("invalid {} query: {e}", spec.name))
+                    });
+
+                    match process_file(directory, &path, &mut parser, query, spec, min_args, &mut local_bucket) {
+                        Ok(count) => local_count += count,
+                        Err(e) => eprintln!("error scanning {}: {e}", path.display()),
+                    }
+                }
+                let _ = tx.send((local_bucket, local_count));
+            });
+        }
+        drop(tx);
+    });
+
+    let mut bucket = TopK::new(top, rank);
+    let mut total_files = 0;
+    for (local_bucket, local_count) in rx {
+        bucket.merge(local_bucket);
+        total_files += local_count;
+    }
+
+    Ok((bucket, total_files))
+}
+
+fn collect_source_files(
+    directory: &Path,
+    no_ignore: bool,
+    languages: &[&'static LanguageSpec],
+) -> Vec<(PathBuf, &'static LanguageSpec)> {
+    let mut builder = WalkBuilder::new(directory);
+    builder.follow_links(true);
+    if no_ignore {
+        builder.standard_filters(false);
+    }
+
+    builder
+        .build()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let extension = e.path().extension()?.to_str()?;
+            let spec = languages
+                .iter()
+                .find(|spec| spec.extensions.contains(&extension))?;
+            Some((e.path().to_path_buf(), *spec))
+        })
+        .collect()
+}
+
+/// Splits `items` round-robin into `buckets` roughly-even chunks.
+fn partition<T>(items: Vec<T>, buckets: usize) -> Vec<Vec<T>> {
+    let mut chunks: Vec<Vec<T>> = (0..buckets).map(|_| Vec::new()).collect();
+    for (i, item) in items.into_iter().enumerate() {
+        chunks[i % buckets].push(item);
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partition_round_robins_across_buckets() {
+        let chunks = partition(vec![0, 1, 2, 3, 4, 5, 6], 3);
+        assert_eq!(chunks, vec![vec![0, 3, 6], vec![1, 4], vec![2, 5]]);
+    }
+
+    #[test]
+    fn partition_handles_fewer_items_than_buckets() {
+        let chunks = partition(vec![0, 1], 4);
+        assert_eq!(chunks, vec![vec![0], vec![1], vec![], vec![]]);
+    }
+
+    #[test]
+    fn partition_handles_empty_input() {
+        let chunks = partition(Vec::<i32>::new(), 3);
+        let expected: Vec<Vec<i32>> = vec![vec![], vec![], vec![]];
+        assert_eq!(chunks, expected);
+    }
+}
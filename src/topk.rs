@@ -0,0 +1,117 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::rank::RankConfig;
+use crate::FnInfo;
+
+/// Keeps at most `cap` entries (the highest-ranked ones seen so far,
+/// per `rank`) when `cap` is set, or behaves like an ordinary unbounded
+/// heap when it isn't. Internally this is a min-heap over each item's
+/// score so the weakest candidate sits at the root and can be evicted in
+/// O(log n), keeping memory at O(cap) regardless of how many candidates
+/// are pushed.
+pub struct TopK {
+    cap: Option<usize>,
+    rank: RankConfig,
+    heap: BinaryHeap<Reverse<(usize, FnInfo)>>,
+}
+
+impl TopK {
+    pub fn new(cap: Option<usize>, rank: RankConfig) -> Self {
+        TopK {
+            cap,
+            rank,
+            heap: BinaryHeap::new(),
+        }
+    }
+
+    pub fn push(&mut self, item: FnInfo) {
+        let score = self.rank.score(&item);
+        self.heap.push(Reverse((score, item)));
+        if let Some(cap) = self.cap {
+            if self.heap.len() > cap {
+                self.heap.pop();
+            }
+        }
+    }
+
+    /// Folds another worker's results into this one, respecting `cap`.
+    pub fn merge(&mut self, other: TopK) {
+        for Reverse((_, item)) in other.heap {
+            self.push(item);
+        }
+    }
+
+    /// Drains the heap into a `Vec` sorted by descending rank.
+    pub fn into_sorted_vec(self) -> Vec<FnInfo> {
+        let mut results: Vec<(usize, FnInfo)> =
+            self.heap.into_iter().map(|Reverse(entry)| entry).collect();
+        results.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| b.1.cmp(&a.1)));
+        results.into_iter().map(|(_, item)| item).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rank::SortKey;
+    use crate::SelfKind;
+
+    fn fn_info(name: &str, arity: usize) -> FnInfo {
+        FnInfo {
+            path: format!("{name}.rs").into(),
+            name: name.to_string(),
+            arity,
+            line: 1,
+            generics: 0,
+            lifetimes: 0,
+            has_return_type: false,
+            self_kind: SelfKind::None,
+        }
+    }
+
+    fn rank() -> RankConfig {
+        RankConfig {
+            key: SortKey::Arity,
+            weighted: false,
+        }
+    }
+
+    #[test]
+    fn push_evicts_lowest_score_past_cap() {
+        let mut topk = TopK::new(Some(2), rank());
+        topk.push(fn_info("a", 1));
+        topk.push(fn_info("b", 3));
+        topk.push(fn_info("c", 2));
+
+        let names: Vec<String> = topk.into_sorted_vec().into_iter().map(|f| f.name).collect();
+        assert_eq!(names, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn into_sorted_vec_orders_by_descending_score() {
+        let mut topk = TopK::new(None, rank());
+        topk.push(fn_info("low", 1));
+        topk.push(fn_info("high", 5));
+        topk.push(fn_info("mid", 3));
+
+        let names: Vec<String> = topk.into_sorted_vec().into_iter().map(|f| f.name).collect();
+        assert_eq!(names, vec!["high", "mid", "low"]);
+    }
+
+    #[test]
+    fn merge_respects_cap_across_both_sources() {
+        let mut a = TopK::new(Some(2), rank());
+        a.push(fn_info("a1", 1));
+        a.push(fn_info("a2", 4));
+
+        let mut b = TopK::new(Some(2), rank());
+        b.push(fn_info("b1", 2));
+        b.push(fn_info("b2", 3));
+
+        a.merge(b);
+
+        let names: Vec<String> = a.into_sorted_vec().into_iter().map(|f| f.name).collect();
+        assert_eq!(names, vec!["a2", "b2"]);
+    }
+}